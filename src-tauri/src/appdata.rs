@@ -6,9 +6,34 @@ use crate::GMOD_APP_ID;
 use lazy_static::lazy_static;
 use parking_lot::{RwLock, RwLockReadGuard};
 use serde::{Deserialize, Serialize};
-use steamworks::PublishedFileId;
+use steamworks::{AppId, PublishedFileId};
 use tauri::Params;
 
+/// A managed Source-engine game: its Steam app id, where it's installed, and
+/// where its workshop content gets extracted to. The app/addon/bundle flow
+/// operates on whichever profile is active, instead of hardcoding GMod.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct AppProfile {
+	pub app_id: AppId,
+	pub name: String,
+	pub install_path: Option<PathBuf>,
+	pub workshop_content_dir: Option<PathBuf>,
+	/// Path, relative to `install_path`, where installed addons live (e.g.
+	/// `GarrysMod/addons`). Used to sanity-check a user-picked install path.
+	pub addon_subpath: PathBuf,
+}
+impl AppProfile {
+	fn gmod() -> Self {
+		Self {
+			app_id: GMOD_APP_ID,
+			name: "Garry's Mod".to_string(),
+			install_path: None,
+			workshop_content_dir: None,
+			addon_subpath: PathBuf::from("GarrysMod/addons"),
+		}
+	}
+}
+
 lazy_static! {
 	static ref USER_DATA_DIR: PathBuf = dirs_next::data_dir()
 		.unwrap_or_else(|| std::env::current_exe().unwrap_or_else(|_| std::env::temp_dir()))
@@ -24,10 +49,18 @@ lazy_static! {
 #[serde(default)]
 pub struct Settings {
 	pub temp: Option<PathBuf>,
-	pub gmod: Option<PathBuf>,
 	pub user_data: Option<PathBuf>,
 	pub downloads: Option<PathBuf>,
 
+	pub app_profiles: Vec<AppProfile>,
+	pub active_app_id: AppId,
+
+	/// Pre-migration GMod path from settings written before app profiles
+	/// existed. Only read once, via [`Settings::migrate_legacy_gmod_path`],
+	/// then never written back out.
+	#[serde(rename = "gmod", skip_serializing)]
+	legacy_gmod_path: Option<PathBuf>,
+
 	pub sounds: bool,
 
 	pub window_size: (f64, f64),
@@ -46,10 +79,13 @@ impl Default for Settings {
 	fn default() -> Self {
 		Self {
 			temp: None,
-			gmod: None,
 			user_data: None,
 			downloads: None,
 
+			app_profiles: vec![AppProfile::gmod()],
+			active_app_id: GMOD_APP_ID,
+			legacy_gmod_path: None,
+
 			extract_destination: ExtractDestination::default(),
 			sounds: true,
 
@@ -76,6 +112,7 @@ impl Settings {
 	fn load(sanitize: bool) -> Result<Settings, anyhow::Error> {
 		let contents = std::fs::read_to_string(&*APP_SETTINGS_PATH)?;
 		let mut settings: Settings = serde_json::de::from_str(&contents)?;
+		settings.migrate_legacy_gmod_path();
 		if sanitize {
 			settings.sanitize();
 		}
@@ -86,10 +123,37 @@ impl Settings {
 		Ok(serde_json::ser::to_writer(File::create(&*APP_SETTINGS_PATH)?, self)?)
 	}
 
+	/// One-time migration of the pre-app-profiles `gmod` settings field into
+	/// the seeded GMod profile's `install_path`, so users with a custom path
+	/// configured before this version don't silently lose it.
+	fn migrate_legacy_gmod_path(&mut self) {
+		if let Some(legacy_gmod_path) = self.legacy_gmod_path.take() {
+			if let Some(profile) = self.app_profiles.iter_mut().find(|profile| profile.app_id == GMOD_APP_ID) {
+				if profile.install_path.is_none() {
+					profile.install_path = Some(legacy_gmod_path);
+				}
+			}
+		}
+	}
+
 	pub fn sanitize(&mut self) {
 		self.destinations.retain(|dir| dir.is_absolute() && dir.is_dir());
 		self.my_workshop_local_paths.retain(|_, dir| dir.is_absolute() && dir.is_dir());
 
+		if self.app_profiles.is_empty() {
+			self.app_profiles.push(AppProfile::gmod());
+		}
+		for profile in self.app_profiles.iter_mut() {
+			if let Some(ref install_path) = profile.install_path {
+				if !install_path.is_absolute() || !install_path.is_dir() {
+					profile.install_path = None;
+				}
+			}
+		}
+		if !self.app_profiles.iter().any(|profile| profile.app_id == self.active_app_id) {
+			self.active_app_id = self.app_profiles[0].app_id;
+		}
+
 		match &self.extract_destination {
 			ExtractDestination::Directory(path) => {
 				if self.create_folder_on_extract || !path.is_dir() {
@@ -107,7 +171,7 @@ impl Settings {
 				}
 			}
 			ExtractDestination::Addons => {
-				if app_data!().gmod_dir().is_none() {
+				if app_data!().app_dir(self.active_app_id).is_none() {
 					self.extract_destination = ExtractDestination::default();
 				}
 			}
@@ -125,8 +189,8 @@ pub struct AppData {
 
 	#[serde(serialize_with = "serde_temp_dir")]
 	temp_dir: PathBuf,
-	#[serde(serialize_with = "serde_gmod_dir")]
-	gmod_dir: Option<PathBuf>,
+	#[serde(serialize_with = "serde_app_dir")]
+	app_dir: Option<PathBuf>,
 	#[serde(serialize_with = "serde_user_data_dir")]
 	user_data_dir: PathBuf,
 	#[serde(serialize_with = "serde_downloads_dir")]
@@ -142,7 +206,7 @@ impl AppData {
 			// Placeholders
 			temp_dir: PathBuf::new(),
 			user_data_dir: PathBuf::new(),
-			gmod_dir: None,
+			app_dir: None,
 			downloads_dir: None,
 		}
 	}
@@ -151,26 +215,44 @@ impl AppData {
 		webview_emit!("UpdateAppData", self);
 	}
 
-	pub fn gmod_dir(&self) -> Option<PathBuf> {
-		if let Some(ref gmod) = self.settings.read().gmod {
-			if gmod.is_dir() {
-				return Some(gmod.to_owned());
+	/// The [`AppProfile`] the user currently has active (GMod by default).
+	pub fn current_app(&self) -> AppProfile {
+		let settings = self.settings.read();
+		settings
+			.app_profiles
+			.iter()
+			.find(|profile| profile.app_id == settings.active_app_id)
+			.cloned()
+			.unwrap_or_else(AppProfile::gmod)
+	}
+
+	/// Resolves the install directory for a managed app: the user's override
+	/// if set, otherwise whatever Steam reports for that app id.
+	pub fn app_dir(&self, app_id: AppId) -> Option<PathBuf> {
+		if let Some(profile) = self.settings.read().app_profiles.iter().find(|profile| profile.app_id == app_id) {
+			if let Some(ref install_path) = profile.install_path {
+				if install_path.is_dir() {
+					return Some(install_path.to_owned());
+				}
 			}
 		}
 
 		if !steam!().connected() {
-			return steamlocate::SteamDir::locate()
-				.and_then(|mut steam_dir| steam_dir.app(&GMOD_APP_ID.0).and_then(|steam_app| Some(steam_app.path.to_owned())));
+			return steamlocate::SteamDir::locate().and_then(|mut steam_dir| steam_dir.app(&app_id.0).and_then(|steam_app| Some(steam_app.path.to_owned())));
 		}
 
-		let gmod: PathBuf = steam!().client().apps().app_install_dir(GMOD_APP_ID).into();
-		if gmod.is_dir() {
-			Some(gmod)
+		let install_dir: PathBuf = steam!().client().apps().app_install_dir(app_id).into();
+		if install_dir.is_dir() {
+			Some(install_dir)
 		} else {
 			None
 		}
 	}
 
+	pub fn gmod_dir(&self) -> Option<PathBuf> {
+		self.app_dir(GMOD_APP_ID)
+	}
+
 	pub fn temp_dir(&self) -> RwLockCow<'_, PathBuf> {
 		let lock = self.settings.read();
 		if let Some(ref temp) = lock.temp {
@@ -250,7 +332,10 @@ pub fn update_settings(mut settings: Settings) {
 
 	ignore! { settings.save() };
 
-	let rediscover_addons = app_data!().settings.read().gmod != settings.gmod;
+	let rediscover_addons = app_data!().current_app() != {
+		let active_app_id = settings.active_app_id;
+		settings.app_profiles.iter().find(|profile| profile.app_id == active_app_id).cloned().unwrap_or_else(AppProfile::gmod)
+	};
 
 	*app_data!().settings.write() = settings;
 
@@ -263,9 +348,17 @@ pub fn update_settings(mut settings: Settings) {
 }
 
 #[tauri::command]
-pub fn validate_gmod(mut path: PathBuf) -> bool {
-	path.push("GarrysMod");
-	path.push("addons");
+pub fn validate_app_dir(mut path: PathBuf, app_id: AppId) -> bool {
+	let subpath = app_data!()
+		.settings
+		.read()
+		.app_profiles
+		.iter()
+		.find(|profile| profile.app_id == app_id)
+		.map(|profile| profile.addon_subpath.clone())
+		.unwrap_or_else(|| PathBuf::from("addons"));
+
+	path.push(subpath);
 	path.is_absolute() && path.is_dir()
 }
 
@@ -275,11 +368,11 @@ pub fn window_resized(width: f64, height: f64) {
 	ignore! { app_data!().settings.read().save() };
 }
 
-fn serde_gmod_dir<S>(_: &Option<PathBuf>, serializer: S) -> Result<S::Ok, S::Error>
+fn serde_app_dir<S>(_: &Option<PathBuf>, serializer: S) -> Result<S::Ok, S::Error>
 where
 	S: serde::Serializer,
 {
-	app_data!().gmod_dir().serialize(serializer)
+	app_data!().app_dir(app_data!().settings.read().active_app_id).serialize(serializer)
 }
 
 fn serde_temp_dir<S>(_: &PathBuf, serializer: S) -> Result<S::Ok, S::Error>
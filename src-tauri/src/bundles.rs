@@ -1,7 +1,9 @@
-use std::{collections::{HashMap, HashSet}, fs::File, hash::Hash, io::{Read, BufReader, BufWriter}, path::PathBuf};
+use std::{collections::{HashMap, HashSet}, fs::File, hash::Hash, io::BufReader, path::PathBuf};
 
 lazy_static! {
 	static ref RE_BUNDLE_DATA: Regex = regex::RegexBuilder::new(r#"^[ \t]*(?:(?:("|'|\[(=*)\[)(\d+)(?:\1|\]\2\]))|--#[ \t]*+(.+?)(?:[ \t]+(.+)|$))"#).multi_line(true).build().unwrap();
+	static ref RE_ADD_WORKSHOP: Regex = regex::RegexBuilder::new(r#"resource\.AddWorkshop\s*\(\s*("|'|\[(=*)\[)(\d+)(?:\1|\]\2\])\s*\)"#).build().unwrap();
+	static ref RE_ID_LIST: Regex = regex::Regex::new(r#"\d+"#).unwrap();
 }
 
 use chrono::Utc;
@@ -10,12 +12,14 @@ use regex::Regex;
 use serde::{Deserialize, Serialize};
 use steamworks::PublishedFileId;
 
+use crate::status::StatusObj;
+
 enum BundleError {
 	ParseError,
 	NoItemsFound,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 struct BundleItem {
 	id: PublishedFileId,
 	added: chrono::DateTime<Utc>,
@@ -37,20 +41,26 @@ impl Ord for BundleItem {
 	}
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 struct BundleCollectionLink {
 	id: PublishedFileId,
 	include: Vec<PublishedFileId>,
-	exclude: Vec<PublishedFileId>
+	exclude: Vec<PublishedFileId>,
+	/// Collection members the user excluded that have since dropped out of
+	/// the collection entirely. Kept separate from `exclude` so a refresh
+	/// can tell "still excluded, still there" apart from "excluded, left,
+	/// and has now come back" (the latter is what `reappeared_excluded`
+	/// reports in [`CollectionDelta`]).
+	missing_excluded: Vec<PublishedFileId>,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 struct Bundle {
 	id: u16,
 	name: String,
 	updated: chrono::DateTime<Utc>,
 	collection: Option<BundleCollectionLink>,
-	items: Vec<PublishedFileId>,
+	items: Vec<BundleItem>,
 }
 impl PartialEq for Bundle {
 	fn eq(&self, other: &Self) -> bool {
@@ -68,58 +78,163 @@ impl Ord for Bundle {
 		self.updated.cmp(&other.updated)
 	}
 }
-impl Bundle {
-	pub fn import(src: String) -> Result<Bundle, BundleError> {
-		let mut bundle_start = false;
-
-		let mut name = String::new();
-		let mut collection: Option<BundleCollectionLink> = None;
-		let mut updated = chrono::Utc::now();
-		let mut items = Vec::with_capacity(4096);
-
-		for data in RE_BUNDLE_DATA.captures_iter(&src) {
-			if let Some(key) = data.get(4) {
-				if key.as_str() == "bundle" {
-					if !bundle_start {
-						bundle_start = true;
-					} else {
-						break;
-					}
+/// Where the IDs that make up an imported [`Bundle`] come from.
+///
+/// This is the dispatch point for everything that isn't our own `--# bundle`
+/// export format: a linked Steam Workshop collection, a server's
+/// `resource.AddWorkshop("...")` script, or a plain list of IDs pasted from a
+/// forum post. All variants funnel into the same [`Bundle`].
+pub enum BundleSource {
+	GmpublisherLua(String),
+	WorkshopCollection(PublishedFileId),
+	RawAddWorkshop(String),
+	IdList(String),
+}
+
+/// Pulls every `resource.AddWorkshop("id")` call's id out of a raw server
+/// script. Pure so it can be covered by a unit test without touching Steam.
+fn parse_raw_add_workshop(src: &str) -> Vec<PublishedFileId> {
+	RE_ADD_WORKSHOP
+		.captures_iter(src)
+		.filter_map(|capture| capture.get(3))
+		.filter_map(|id| id.as_str().parse::<u64>().ok())
+		.map(PublishedFileId)
+		.collect()
+}
+
+/// Pulls every bare number out of a pasted list of workshop IDs. Pure so it
+/// can be covered by a unit test without touching Steam.
+fn parse_id_list(src: &str) -> Vec<PublishedFileId> {
+	RE_ID_LIST.find_iter(src).filter_map(|m| m.as_str().parse::<u64>().ok()).map(PublishedFileId).collect()
+}
+
+/// What [`parse_gmpublisher_lua`] extracted from a `--#`-annotated export,
+/// before the linked collection (if any) is fetched from Steam.
+struct ParsedGmpublisherLua {
+	name: String,
+	collection_id: Option<PublishedFileId>,
+	updated: chrono::DateTime<Utc>,
+	items: Vec<PublishedFileId>,
+}
+
+/// Parses the `--#` annotation lines and quoted item list out of a
+/// gmpublisher-exported Lua file. Pure so it can be covered by a unit test
+/// without touching Steam; [`Bundle::import_gmpublisher_lua`] is the only
+/// caller and handles fetching the linked collection's members afterwards.
+fn parse_gmpublisher_lua(src: &str) -> ParsedGmpublisherLua {
+	let mut bundle_start = false;
+
+	let mut name = String::new();
+	let mut collection_id = None;
+	let mut updated = chrono::Utc::now();
+	let mut items = Vec::with_capacity(4096);
+
+	for data in RE_BUNDLE_DATA.captures_iter(src) {
+		if let Some(key) = data.get(4) {
+			if key.as_str() == "bundle" {
+				if !bundle_start {
+					bundle_start = true;
 				} else {
-					let val = match data.get(5) {
-						Some(val) => val,
-						None => continue
-					};
-					match key.as_str() {
-						"name" => name = val.as_str().to_string(),
-						"collection" => if let Ok(id) = val.as_str().parse::<u64>() {
-							collection = Some(BundleCollectionLink {
-								id: PublishedFileId(id),
-								include: Vec::with_capacity(4096),
-								exclude: Vec::new(),
-							});
-						},
-						"updated" => if let Ok(parsed) = chrono::DateTime::parse_from_rfc2822(val.as_str()) {
-							updated = parsed.with_timezone(&Utc);
-						},
-						_ => {}
-					}
+					break;
 				}
-			} else if let Some(item) = data.get(3) {
-				items.push(PublishedFileId(match item.as_str().parse::<u64>() {
-					Ok(id) => id,
-					Err(_) => continue
-				}));
 			} else {
-				#[cfg(debug_assertions)]
-				panic!("Unexpected match when parsing bundle data");
+				let val = match data.get(5) {
+					Some(val) => val,
+					None => continue
+				};
+				match key.as_str() {
+					"name" => name = val.as_str().to_string(),
+					"collection" => if let Ok(id) = val.as_str().parse::<u64>() {
+						collection_id = Some(PublishedFileId(id));
+					},
+					"updated" => if let Ok(parsed) = chrono::DateTime::parse_from_rfc2822(val.as_str()) {
+						updated = parsed.with_timezone(&Utc);
+					},
+					_ => {}
+				}
 			}
+		} else if let Some(item) = data.get(3) {
+			items.push(PublishedFileId(match item.as_str().parse::<u64>() {
+				Ok(id) => id,
+				Err(_) => continue
+			}));
+		} else {
+			// Unknown token; skip it rather than panicking on malformed input.
+			continue;
 		}
+	}
+
+	ParsedGmpublisherLua { name, collection_id, updated, items }
+}
 
-		if items.is_empty() {
+impl Bundle {
+	pub fn import(source: BundleSource) -> Result<Bundle, BundleError> {
+		match source {
+			BundleSource::GmpublisherLua(src) => Self::import_gmpublisher_lua(src),
+			BundleSource::WorkshopCollection(id) => Self::import_workshop_collection(id),
+			BundleSource::RawAddWorkshop(src) => Self::import_raw_add_workshop(src),
+			BundleSource::IdList(src) => Self::import_id_list(src),
+		}
+	}
+
+	fn finalize(name: String, updated: chrono::DateTime<Utc>, collection: Option<BundleCollectionLink>, items: Vec<PublishedFileId>) -> Result<Bundle, BundleError> {
+		let collection_items = collection.as_ref().map_or(0, |c| c.include.len());
+		if items.is_empty() && collection_items == 0 {
 			return Err(BundleError::NoItemsFound);
 		}
 
+		let added = Utc::now();
+		let items = items.into_iter().map(|id| BundleItem { id, added }).collect();
+
+		let id = BUNDLES.lock().id + 1; // TODO potential deadlock?
+		Ok(Bundle {
+			id,
+			name,
+			updated,
+			collection,
+			items,
+		})
+	}
+
+	/// Fetches every member of a linked Steam Workshop collection and tracks
+	/// them all as included, with nothing yet manually excluded. The members
+	/// live only in `collection.include` (mirrored into the export by
+	/// `export_inner`) and not in `items`, so they aren't written twice.
+	fn import_workshop_collection(id: PublishedFileId) -> Result<Bundle, BundleError> {
+		let items = steam!().fetch_collection_items(id).ok_or(BundleError::NoItemsFound)?;
+
+		let collection = Some(BundleCollectionLink {
+			id,
+			include: items,
+			exclude: Vec::new(),
+			missing_excluded: Vec::new(),
+		});
+
+		Self::finalize(String::new(), chrono::Utc::now(), collection, Vec::new())
+	}
+
+	/// Parses a bare `resource.AddWorkshop("id")` server script, i.e. one that
+	/// isn't wrapped in our `--#` export annotations.
+	fn import_raw_add_workshop(src: String) -> Result<Bundle, BundleError> {
+		Self::finalize(String::new(), chrono::Utc::now(), None, parse_raw_add_workshop(&src))
+	}
+
+	/// Parses a newline/comma-separated list of workshop IDs, e.g. pasted
+	/// straight from a forum post. Anything that isn't a number is skipped.
+	fn import_id_list(src: String) -> Result<Bundle, BundleError> {
+		Self::finalize(String::new(), chrono::Utc::now(), None, parse_id_list(&src))
+	}
+
+	fn import_gmpublisher_lua(src: String) -> Result<Bundle, BundleError> {
+		let ParsedGmpublisherLua { name, collection_id, updated, mut items } = parse_gmpublisher_lua(&src);
+
+		let mut collection = collection_id.map(|id| BundleCollectionLink {
+			id,
+			include: Vec::with_capacity(4096),
+			exclude: Vec::new(),
+			missing_excluded: Vec::new(),
+		});
+
 		if let Some(ref mut collection) = collection {
 			if let Some(collection_items) = steam!().fetch_collection_items(collection.id) {
 				for item in collection_items {
@@ -138,17 +253,35 @@ impl Bundle {
 			}
 		}
 
-		let id = BUNDLES.lock().id + 1; // TODO potential deadlock?
-		Ok(Bundle {
-		    id,
-		    name,
-		    updated,
-		    collection,
-		    items,
-		})
+		Self::finalize(name, updated, collection, items)
+	}
+
+	/// Same as [`Bundle::export`] but reports progress over `status_channel`
+	/// via [`StatusObj`], so the frontend can show a progress bar and log
+	/// tail for bundles with thousands of items instead of freezing.
+	pub fn export_with_status(&self, status_channel: &str, item_names: HashMap<PublishedFileId, String>, collection_name: Option<&str>) -> String {
+		StatusObj::progress("Exporting bundle", 0.).emit(status_channel);
+
+		let total = self.items.len() + self.collection.as_ref().map_or(0, |c| c.include.len());
+		let mut done = 0;
+
+		let export = self.export_inner(item_names, collection_name, &mut |item: PublishedFileId| {
+			done += 1;
+			if total > 0 {
+				StatusObj::progress("Exporting bundle", done as f32 / total as f32).emit(status_channel);
+			}
+			StatusObj::log(item.0.to_string()).emit(status_channel);
+		});
+
+		StatusObj::complete().emit(status_channel);
+		export
 	}
 
 	pub fn export(&self, item_names: HashMap<PublishedFileId, String>, collection_name: Option<&str>) -> String {
+		self.export_inner(item_names, collection_name, &mut |_| {})
+	}
+
+	fn export_inner(&self, item_names: HashMap<PublishedFileId, String>, collection_name: Option<&str>, mut on_item: impl FnMut(PublishedFileId)) -> String {
 		// TODO convert these to write!(export, ...)
 
 		let mut export = String::with_capacity(1000000);
@@ -174,12 +307,13 @@ impl Bundle {
 
 		for item in self.items.iter() {
 			export.push('\"');
-			export.push_str(&item.0.to_string());
-			if let Some(name) = item_names.get(item) {
+			export.push_str(&item.id.0.to_string());
+			if let Some(name) = item_names.get(&item.id) {
 				export.push_str("\" -- ");
 				export.push_str(name);
 				export.push('\n');
 			}
+			on_item(item.id);
 		}
 
 		if let Some(ref collection) = self.collection {
@@ -200,6 +334,7 @@ impl Bundle {
 					export.push_str(name);
 					export.push('\n');
 				}
+				on_item(*item);
 			}
 		}
 
@@ -210,37 +345,24 @@ impl Bundle {
 	}
 }
 
-#[derive(Serialize, Deserialize)]
 pub struct Bundles {
 	saved: Vec<Bundle>,
 	id: u16,
+	conn: rusqlite::Connection,
 }
 impl Bundles {
 	pub fn init() -> Self {
-		let mut saved = Vec::new();
-		let mut id = 0;
-
-		std::fs::create_dir_all(&*bundles_path()).expect("Failed to create content generator bundles directory");
-
-		if let Ok(dir) = bundles_path().read_dir() {
-			for entry in dir {
-				ignore! { try_block!({
-					let entry = entry?;
-					let contents: Bundle = bincode::deserialize_from(BufReader::new(File::open(entry.path())?))?;
-					id = id.max(contents.id);
-
-					saved.insert(
-						match saved.binary_search(&contents) {
-							Ok(pos) => pos,
-							Err(pos) => pos,
-						},
-						contents,
-					);
-				}) };
-			}
-		}
+		std::fs::create_dir_all(&*app_data!().user_data_dir()).expect("Failed to create user data directory");
+
+		let conn = crate::db::open(&bundles_db_path()).expect("Failed to open bundles database");
+
+		import_legacy_bincode_bundles(&conn);
 
-		Self { saved, id }
+		let mut saved = load_all(&conn);
+		saved.sort();
+		let id = saved.iter().map(|bundle| bundle.id).max().unwrap_or(0);
+
+		Self { saved, id, conn }
 	}
 }
 
@@ -248,27 +370,359 @@ lazy_static! {
 	pub static ref BUNDLES: Mutex<Bundles> = Mutex::new(Bundles::init());
 }
 
-fn bundles_path() -> PathBuf {
+fn bundles_db_path() -> PathBuf {
+	app_data!().user_data_dir().join("bundles.sqlite")
+}
+
+/// Where bundles used to live before the SQLite migration: one bincode file
+/// per bundle, named after its id.
+fn legacy_bundles_path() -> PathBuf {
 	app_data!().user_data_dir().join("content_generator")
 }
 
+/// One-time importer: if the old per-file bincode bundle directory is still
+/// present, ingest every bundle it contains into the database, then rename
+/// the directory out of the way so this only ever runs once.
+fn import_legacy_bincode_bundles(conn: &rusqlite::Connection) {
+	let legacy_dir = legacy_bundles_path();
+
+	let dir = match legacy_dir.read_dir() {
+		Ok(dir) => dir,
+		Err(_) => return,
+	};
+
+	for entry in dir {
+		ignore! { try_block!({
+			let entry = entry?;
+			let bundle: Bundle = bincode::deserialize_from(BufReader::new(File::open(entry.path())?))?;
+			save_bundle(conn, &bundle)?;
+		}) };
+	}
+
+	ignore! { std::fs::rename(&legacy_dir, legacy_dir.with_extension("imported")) };
+}
+
+fn load_all(conn: &rusqlite::Connection) -> Vec<Bundle> {
+	let mut result = Vec::new();
+
+	ignore! { try_block!({
+		let mut bundles_stmt = conn.prepare("SELECT id, name, updated FROM bundles")?;
+		let mut rows = bundles_stmt.query([])?;
+
+		while let Some(row) = rows.next()? {
+			let id: u16 = row.get(0)?;
+			let name: String = row.get(1)?;
+			let updated: String = row.get(2)?;
+			let updated = chrono::DateTime::parse_from_rfc3339(&updated)?.with_timezone(&Utc);
+
+			let mut items_stmt = conn.prepare("SELECT workshop_id, added FROM bundle_items WHERE bundle_id = ?1 ORDER BY position")?;
+			let items = items_stmt
+				.query_map([id], |row| {
+					let added: String = row.get(1)?;
+					Ok((PublishedFileId(row.get(0)?), added))
+				})?
+				.filter_map(Result::ok)
+				.filter_map(|(id, added)| {
+					let added = chrono::DateTime::parse_from_rfc3339(&added).ok()?.with_timezone(&Utc);
+					Some(BundleItem { id, added })
+				})
+				.collect();
+
+			let collection = match conn.query_row("SELECT collection_id FROM bundle_collections WHERE bundle_id = ?1", [id], |row| row.get::<_, u64>(0)) {
+				Ok(collection_id) => {
+					let mut links_stmt = conn.prepare("SELECT workshop_id, included, missing FROM bundle_collection_links WHERE bundle_id = ?1")?;
+					let mut include = Vec::new();
+					let mut exclude = Vec::new();
+					let mut missing_excluded = Vec::new();
+					let mut rows = links_stmt.query([id])?;
+					while let Some(row) = rows.next()? {
+						let workshop_id = PublishedFileId(row.get(0)?);
+						if row.get::<_, bool>(1)? {
+							include.push(workshop_id);
+						} else if row.get::<_, bool>(2)? {
+							missing_excluded.push(workshop_id);
+						} else {
+							exclude.push(workshop_id);
+						}
+					}
+
+					Some(BundleCollectionLink {
+						id: PublishedFileId(collection_id),
+						include,
+						exclude,
+						missing_excluded,
+					})
+				}
+				Err(rusqlite::Error::QueryReturnedNoRows) => None,
+				Err(err) => return Err(err.into()),
+			};
+
+			result.push(Bundle { id, name, updated, collection, items });
+		}
+
+		Ok(())
+	}) };
+
+	result
+}
+
+fn save_bundle(conn: &rusqlite::Connection, bundle: &Bundle) -> anyhow::Result<()> {
+	conn.execute(
+		"INSERT INTO bundles (id, name, updated) VALUES (?1, ?2, ?3) ON CONFLICT(id) DO UPDATE SET name = excluded.name, updated = excluded.updated",
+		rusqlite::params![bundle.id, bundle.name, bundle.updated.to_rfc3339()],
+	)?;
+
+	conn.execute("DELETE FROM bundle_items WHERE bundle_id = ?1", [bundle.id])?;
+	conn.execute("DELETE FROM bundle_collections WHERE bundle_id = ?1", [bundle.id])?;
+	conn.execute("DELETE FROM bundle_collection_links WHERE bundle_id = ?1", [bundle.id])?;
+
+	for (position, item) in bundle.items.iter().enumerate() {
+		conn.execute(
+			"INSERT INTO bundle_items (bundle_id, position, workshop_id, added) VALUES (?1, ?2, ?3, ?4)",
+			rusqlite::params![bundle.id, position as i64, item.id.0, item.added.to_rfc3339()],
+		)?;
+	}
+
+	if let Some(ref collection) = bundle.collection {
+		conn.execute(
+			"INSERT INTO bundle_collections (bundle_id, collection_id) VALUES (?1, ?2)",
+			rusqlite::params![bundle.id, collection.id.0],
+		)?;
+
+		for item in collection.include.iter() {
+			conn.execute(
+				"INSERT INTO bundle_collection_links (bundle_id, workshop_id, included, missing) VALUES (?1, ?2, 1, 0)",
+				rusqlite::params![bundle.id, item.0],
+			)?;
+		}
+		for item in collection.exclude.iter() {
+			conn.execute(
+				"INSERT INTO bundle_collection_links (bundle_id, workshop_id, included, missing) VALUES (?1, ?2, 0, 0)",
+				rusqlite::params![bundle.id, item.0],
+			)?;
+		}
+		for item in collection.missing_excluded.iter() {
+			conn.execute(
+				"INSERT INTO bundle_collection_links (bundle_id, workshop_id, included, missing) VALUES (?1, ?2, 0, 1)",
+				rusqlite::params![bundle.id, item.0],
+			)?;
+		}
+	}
+
+	Ok(())
+}
+
 #[tauri::command]
-fn get_bundles() -> &'static Vec<Bundle> {
-	unsafe { &*(&BUNDLES.lock().saved as *const _) }
+fn get_bundles() -> Vec<Bundle> {
+	BUNDLES.lock().saved.clone()
 }
 
 #[tauri::command]
 fn update_bundle(bundle: Bundle) -> bool {
 	try_block!({
-		let mut content_generator = BUNDLES.lock();
+		let mut bundles = BUNDLES.lock();
 
-		let f = File::create(bundles_path().join(bundle.id.to_string()))?;
-		bincode::serialize_into(BufWriter::new(f), &bundle)?;
+		save_bundle(&bundles.conn, &bundle)?;
 
-		match content_generator.saved.binary_search(&bundle) {
-			Ok(pos) => content_generator.saved[pos] = bundle,
-			Err(pos) => content_generator.saved.insert(pos, bundle),
+		match bundles.saved.binary_search(&bundle) {
+			Ok(pos) => bundles.saved[pos] = bundle,
+			Err(pos) => bundles.saved.insert(pos, bundle),
 		}
 	})
 	.is_ok()
+}
+
+/// What changed in a linked Steam Workshop collection since it was last
+/// synced with its [`Bundle`].
+#[derive(Serialize)]
+pub struct CollectionDelta {
+	added: Vec<PublishedFileId>,
+	removed: Vec<PublishedFileId>,
+	reappeared_excluded: Vec<PublishedFileId>,
+}
+
+/// Diffs a bundle's stored collection link against a freshly fetched member
+/// list. Returns the delta alongside the `include`/`exclude`/`missing_excluded`
+/// lists it would become if applied: members new to the collection move into
+/// `include`, members that dropped out of the collection are dropped from
+/// `include` entirely, and members the user previously excluded stay excluded
+/// (silently, if they were never gone) or move to `missing_excluded` if they
+/// drop out of the collection — only a `missing_excluded` member coming back
+/// counts as "reappeared".
+fn diff_collection(collection: &BundleCollectionLink, fresh_items: &[PublishedFileId]) -> (CollectionDelta, Vec<PublishedFileId>, Vec<PublishedFileId>, Vec<PublishedFileId>) {
+	let fresh_set: HashSet<PublishedFileId> = fresh_items.iter().copied().collect();
+	let previously_seen: HashSet<PublishedFileId> = collection
+		.include
+		.iter()
+		.chain(collection.exclude.iter())
+		.chain(collection.missing_excluded.iter())
+		.copied()
+		.collect();
+
+	let added: Vec<PublishedFileId> = fresh_items.iter().copied().filter(|id| !previously_seen.contains(id)).collect();
+	let removed: Vec<PublishedFileId> = collection.include.iter().copied().filter(|id| !fresh_set.contains(id)).collect();
+
+	let still_excluded: Vec<PublishedFileId> = collection.exclude.iter().copied().filter(|id| fresh_set.contains(id)).collect();
+	let newly_missing_excluded: Vec<PublishedFileId> = collection.exclude.iter().copied().filter(|id| !fresh_set.contains(id)).collect();
+
+	let reappeared_excluded: Vec<PublishedFileId> = collection.missing_excluded.iter().copied().filter(|id| fresh_set.contains(id)).collect();
+	let still_missing_excluded: Vec<PublishedFileId> = collection.missing_excluded.iter().copied().filter(|id| !fresh_set.contains(id)).collect();
+
+	let new_include: Vec<PublishedFileId> = collection.include.iter().copied().filter(|id| fresh_set.contains(id)).chain(added.iter().copied()).collect();
+	let new_exclude: Vec<PublishedFileId> = still_excluded.into_iter().chain(reappeared_excluded.iter().copied()).collect();
+	let new_missing_excluded: Vec<PublishedFileId> = newly_missing_excluded.into_iter().chain(still_missing_excluded).collect();
+
+	(CollectionDelta { added, removed, reappeared_excluded }, new_include, new_exclude, new_missing_excluded)
+}
+
+/// Re-fetches a bundle's linked collection and diffs it against the stored
+/// `include`/`exclude`/`missing_excluded` lists. The bundle's own `items` are
+/// not consulted, so a member already listed there can still be reported as
+/// added and put into `include`. With `apply: false` this is a preview that
+/// leaves the bundle untouched; with `apply: true` the bundle's collection
+/// link and `updated` timestamp are persisted with the new state.
+///
+/// The `BUNDLES` lock is only held for the lookup and (if applying) the final
+/// persist — never across the `fetch_collection_items` network round-trip,
+/// mirroring `import_workshop_collection`/`import_gmpublisher_lua`, which
+/// fetch before touching `BUNDLES` rather than holding it for the duration.
+#[tauri::command]
+pub fn refresh_bundle_collection(id: u16, apply: bool) -> Result<CollectionDelta, String> {
+	let collection = BUNDLES
+		.lock()
+		.saved
+		.iter()
+		.find(|bundle| bundle.id == id)
+		.ok_or("Bundle not found")?
+		.collection
+		.clone()
+		.ok_or("Bundle has no linked collection")?;
+
+	let fresh_items = steam!().fetch_collection_items(collection.id).ok_or("Failed to fetch the linked collection")?;
+
+	let (delta, new_include, new_exclude, new_missing_excluded) = diff_collection(&collection, &fresh_items);
+
+	if apply {
+		let mut bundles = BUNDLES.lock();
+		let index = bundles.saved.iter().position(|bundle| bundle.id == id).ok_or("Bundle not found")?;
+
+		let mut bundle = bundles.saved[index].clone();
+		bundle.collection = Some(BundleCollectionLink {
+			id: collection.id,
+			include: new_include,
+			exclude: new_exclude,
+			missing_excluded: new_missing_excluded,
+		});
+		bundle.updated = Utc::now();
+
+		// Only mutate `saved` once the write actually succeeds, so a failed
+		// save (locked file, disk full) can't leave the in-memory list out of
+		// sync with what's on disk.
+		save_bundle(&bundles.conn, &bundle).map_err(|e| e.to_string())?;
+
+		bundles.saved.remove(index);
+		// `saved` is kept sorted by `updated` (see `Bundles::init`/`update_bundle`);
+		// re-insert at the position the new `updated` timestamp belongs at.
+		match bundles.saved.binary_search(&bundle) {
+			Ok(pos) => bundles.saved[pos] = bundle,
+			Err(pos) => bundles.saved.insert(pos, bundle),
+		}
+	}
+
+	Ok(delta)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn link(include: &[u64], exclude: &[u64], missing_excluded: &[u64]) -> BundleCollectionLink {
+		BundleCollectionLink {
+			id: PublishedFileId(1),
+			include: include.iter().copied().map(PublishedFileId).collect(),
+			exclude: exclude.iter().copied().map(PublishedFileId).collect(),
+			missing_excluded: missing_excluded.iter().copied().map(PublishedFileId).collect(),
+		}
+	}
+
+	fn ids(raw: &[u64]) -> Vec<PublishedFileId> {
+		raw.iter().copied().map(PublishedFileId).collect()
+	}
+
+	#[test]
+	fn diff_collection_reports_new_members_as_added() {
+		let collection = link(&[1, 2], &[], &[]);
+		let (delta, new_include, new_exclude, new_missing_excluded) = diff_collection(&collection, &ids(&[1, 2, 3]));
+
+		assert_eq!(delta.added, ids(&[3]));
+		assert_eq!(delta.removed, Vec::new());
+		assert_eq!(delta.reappeared_excluded, Vec::new());
+		assert_eq!(new_include, ids(&[1, 2, 3]));
+		assert_eq!(new_exclude, Vec::new());
+		assert_eq!(new_missing_excluded, Vec::new());
+	}
+
+	#[test]
+	fn diff_collection_reports_dropped_members_as_removed() {
+		let collection = link(&[1, 2], &[], &[]);
+		let (delta, new_include, _, _) = diff_collection(&collection, &ids(&[1]));
+
+		assert_eq!(delta.removed, ids(&[2]));
+		assert_eq!(new_include, ids(&[1]));
+	}
+
+	/// Regression test for the bug where a no-op refresh (nothing actually
+	/// changed in the collection) reported every still-excluded item as
+	/// "reappeared".
+	#[test]
+	fn diff_collection_noop_refresh_does_not_report_still_excluded_as_reappeared() {
+		let collection = link(&[1], &[2], &[]);
+		let (delta, _, new_exclude, new_missing_excluded) = diff_collection(&collection, &ids(&[1, 2]));
+
+		assert_eq!(delta.reappeared_excluded, Vec::new());
+		assert_eq!(new_exclude, ids(&[2]));
+		assert_eq!(new_missing_excluded, Vec::new());
+	}
+
+	#[test]
+	fn diff_collection_excluded_member_leaving_moves_to_missing_excluded() {
+		let collection = link(&[1], &[2], &[]);
+		let (delta, _, new_exclude, new_missing_excluded) = diff_collection(&collection, &ids(&[1]));
+
+		assert_eq!(delta.reappeared_excluded, Vec::new());
+		assert_eq!(new_exclude, Vec::new());
+		assert_eq!(new_missing_excluded, ids(&[2]));
+	}
+
+	#[test]
+	fn diff_collection_only_reports_reappeared_for_members_that_actually_came_back() {
+		let collection = link(&[1], &[], &[2]);
+		let (delta, _, new_exclude, new_missing_excluded) = diff_collection(&collection, &ids(&[1, 2]));
+
+		assert_eq!(delta.reappeared_excluded, ids(&[2]));
+		assert_eq!(new_exclude, ids(&[2]));
+		assert_eq!(new_missing_excluded, Vec::new());
+	}
+
+	#[test]
+	fn parse_raw_add_workshop_extracts_every_id() {
+		let src = r#"resource.AddWorkshop("123") resource.AddWorkshop('456')"#;
+		assert_eq!(parse_raw_add_workshop(src), ids(&[123, 456]));
+	}
+
+	#[test]
+	fn parse_id_list_skips_non_numeric_tokens() {
+		let src = "123, 456\nfoo\n789";
+		assert_eq!(parse_id_list(src), ids(&[123, 456, 789]));
+	}
+
+	#[test]
+	fn parse_gmpublisher_lua_reads_name_collection_and_items() {
+		let src = "--# bundle\n--# name My Bundle\n--# collection 999\n\"111\"\n\"222\"\n";
+		let parsed = parse_gmpublisher_lua(src);
+
+		assert_eq!(parsed.name, "My Bundle");
+		assert_eq!(parsed.collection_id, Some(PublishedFileId(999)));
+		assert_eq!(parsed.items, ids(&[111, 222]));
+	}
 }
\ No newline at end of file
@@ -20,6 +20,11 @@ where
 		crate::steam::workshop::browse_my_workshop,
 
 		crate::addon_size_analyzer::free_addon_size_analyzer,
+		crate::duplicate_finder::find_duplicate_addon_files,
+
+		crate::status::resolve_status_prompt,
+
+		crate::bundles::refresh_bundle_collection,
 	]
 }
 
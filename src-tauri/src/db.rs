@@ -0,0 +1,29 @@
+use std::path::Path;
+
+use rusqlite::Connection;
+
+/// Forward-only SQL migrations, applied in order and tracked via SQLite's
+/// built-in `user_version` pragma so a given database only ever replays the
+/// steps it hasn't already seen. New schema changes are added by appending a
+/// new `.sql` file here, never by editing an existing one.
+const MIGRATIONS: &[&str] = &[
+	include_str!("migrations/0001_initial.sql"),
+	include_str!("migrations/0002_collection_link_missing.sql"),
+];
+
+pub fn open(path: &Path) -> rusqlite::Result<Connection> {
+	let mut conn = Connection::open(path)?;
+	migrate(&mut conn)?;
+	Ok(conn)
+}
+
+fn migrate(conn: &mut Connection) -> rusqlite::Result<()> {
+	let current_version: usize = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+
+	let tx = conn.transaction()?;
+	for migration in MIGRATIONS.iter().skip(current_version) {
+		tx.execute_batch(migration)?;
+	}
+	tx.pragma_update(None, "user_version", MIGRATIONS.len())?;
+	tx.commit()
+}
@@ -0,0 +1,195 @@
+use std::{
+	collections::HashMap,
+	fs,
+	path::{Path, PathBuf},
+};
+
+use rayon::iter::{ParallelBridge, ParallelIterator};
+use serde::Serialize;
+use twox_hash::xxh3::hash64;
+
+/// Files at or below this many bytes are too small to be worth deduplicating
+/// (empty placeholders, stub `.txt` files, etc.) and just add noise.
+const MIN_FILE_SIZE: u64 = 4;
+
+struct CandidateFile {
+	addon: String,
+	canonical_path: String,
+	len: u64,
+	hash: u64,
+}
+
+#[derive(Serialize)]
+pub struct DuplicateCluster {
+	canonical_path: String,
+	size: u64,
+	addons: Vec<String>,
+	wasted_bytes: u64,
+}
+
+#[derive(Serialize, Default)]
+pub struct DuplicateReport {
+	clusters: Vec<DuplicateCluster>,
+	per_addon_contribution: HashMap<String, u64>,
+	total_reclaimable_bytes: u64,
+}
+
+/// Lowercases a file's path relative to its addon root and normalizes path
+/// separators, so the same file under different addons hashes to the same
+/// canonical key regardless of platform.
+fn canonical_key(root: &Path, path: &Path) -> String {
+	path.strip_prefix(root)
+		.unwrap_or(path)
+		.to_string_lossy()
+		.replace('\\', "/")
+		.to_lowercase()
+}
+
+fn scan_addon_files(addon: &str, root: &Path) -> Vec<CandidateFile> {
+	glob::glob(&format!("{}/**/*", root.display()))
+		.into_iter()
+		.flatten()
+		.filter_map(Result::ok)
+		.par_bridge()
+		.filter_map(|path| {
+			let metadata = fs::metadata(&path).ok()?;
+			if !metadata.is_file() || metadata.len() <= MIN_FILE_SIZE {
+				return None;
+			}
+
+			let contents = fs::read(&path).ok()?;
+
+			Some(CandidateFile {
+				addon: addon.to_string(),
+				canonical_path: canonical_key(root, &path),
+				len: metadata.len(),
+				hash: hash64(&contents),
+			})
+		})
+		.collect()
+}
+
+/// Scans every installed/extracted addon and groups files that are
+/// byte-for-byte identical across two or more addons, so the user can see
+/// where disk space is being wasted on duplicated content.
+#[tauri::command]
+pub fn find_duplicate_addon_files() -> DuplicateReport {
+	let addon_roots = game_addons!().installed_addon_paths();
+
+	let candidates: Vec<CandidateFile> = addon_roots
+		.iter()
+		.flat_map(|(addon, root)| scan_addon_files(addon, root))
+		.collect();
+
+	aggregate_duplicates(candidates)
+}
+
+/// Groups candidate files by content (hash + length) and turns every group
+/// shared by two or more addons into a [`DuplicateCluster`], tallying up
+/// per-addon and total wasted bytes along the way. Pure so it can be covered
+/// by a unit test without touching the filesystem.
+fn aggregate_duplicates(candidates: Vec<CandidateFile>) -> DuplicateReport {
+	let mut groups: HashMap<(u64, u64), Vec<CandidateFile>> = HashMap::new();
+	for candidate in candidates {
+		groups.entry((candidate.hash, candidate.len)).or_default().push(candidate);
+	}
+
+	let mut report = DuplicateReport::default();
+
+	for ((_, len), members) in groups {
+		let distinct_addons: HashMap<&str, ()> = members.iter().map(|m| (m.addon.as_str(), ())).collect();
+		if distinct_addons.len() < 2 {
+			continue;
+		}
+
+		let wasted_bytes = len * (members.len() as u64 - 1);
+		report.total_reclaimable_bytes += wasted_bytes;
+
+		for addon in distinct_addons.keys() {
+			*report.per_addon_contribution.entry((*addon).to_string()).or_insert(0) += wasted_bytes / members.len() as u64;
+		}
+
+		report.clusters.push(DuplicateCluster {
+			canonical_path: members[0].canonical_path.clone(),
+			size: len,
+			addons: members.into_iter().map(|m| m.addon).collect(),
+			wasted_bytes,
+		});
+	}
+
+	report.clusters.sort_by(|a, b| b.wasted_bytes.cmp(&a.wasted_bytes));
+
+	report
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn candidate(addon: &str, canonical_path: &str, len: u64, hash: u64) -> CandidateFile {
+		CandidateFile {
+			addon: addon.to_string(),
+			canonical_path: canonical_path.to_string(),
+			len,
+			hash,
+		}
+	}
+
+	#[test]
+	fn aggregate_duplicates_ignores_files_unique_to_one_addon() {
+		let report = aggregate_duplicates(vec![candidate("addon_a", "materials/foo.vtf", 100, 1)]);
+
+		assert!(report.clusters.is_empty());
+		assert_eq!(report.total_reclaimable_bytes, 0);
+	}
+
+	#[test]
+	fn aggregate_duplicates_clusters_identical_files_across_addons() {
+		let report = aggregate_duplicates(vec![
+			candidate("addon_a", "materials/foo.vtf", 100, 1),
+			candidate("addon_b", "materials/foo.vtf", 100, 1),
+			candidate("addon_c", "materials/foo.vtf", 100, 1),
+		]);
+
+		assert_eq!(report.clusters.len(), 1);
+		let cluster = &report.clusters[0];
+		assert_eq!(cluster.size, 100);
+		assert_eq!(cluster.wasted_bytes, 200);
+		assert_eq!(cluster.addons.len(), 3);
+		assert_eq!(report.total_reclaimable_bytes, 200);
+	}
+
+	#[test]
+	fn aggregate_duplicates_splits_wasted_bytes_evenly_per_addon() {
+		let report = aggregate_duplicates(vec![
+			candidate("addon_a", "materials/foo.vtf", 100, 1),
+			candidate("addon_b", "materials/foo.vtf", 100, 1),
+		]);
+
+		assert_eq!(report.per_addon_contribution.get("addon_a"), Some(&50));
+		assert_eq!(report.per_addon_contribution.get("addon_b"), Some(&50));
+	}
+
+	#[test]
+	fn aggregate_duplicates_does_not_cluster_files_with_different_content() {
+		let report = aggregate_duplicates(vec![
+			candidate("addon_a", "materials/foo.vtf", 100, 1),
+			candidate("addon_b", "materials/foo.vtf", 100, 2),
+		]);
+
+		assert!(report.clusters.is_empty());
+	}
+
+	#[test]
+	fn aggregate_duplicates_sorts_clusters_by_wasted_bytes_descending() {
+		let report = aggregate_duplicates(vec![
+			candidate("addon_a", "small.txt", 10, 1),
+			candidate("addon_b", "small.txt", 10, 1),
+			candidate("addon_a", "big.txt", 1000, 2),
+			candidate("addon_b", "big.txt", 1000, 2),
+		]);
+
+		assert_eq!(report.clusters[0].canonical_path, "big.txt");
+		assert_eq!(report.clusters[1].canonical_path, "small.txt");
+	}
+}
@@ -0,0 +1,87 @@
+use std::{collections::HashMap, sync::mpsc};
+
+use lazy_static::lazy_static;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+
+use crate::webview_emit;
+
+/// A single event in a long-running operation's progress stream, emitted on
+/// the operation's own `webview_emit!` channel instead of a bespoke event, so
+/// the frontend gets one consistent progress bar, log tail and prompt UI.
+///
+/// Only bundle export (`Bundle::export_with_status`) reports through it;
+/// addon extraction and workshop fetches do not emit `StatusObj` events.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StatusObj {
+	pub label: Option<String>,
+	pub progress: Option<f32>,
+	pub complete: bool,
+	pub log_line: Option<String>,
+	pub error: Option<String>,
+	pub prompt_items: Option<Vec<String>>,
+}
+
+lazy_static! {
+	static ref PENDING_PROMPTS: Mutex<HashMap<String, mpsc::Sender<String>>> = Mutex::new(HashMap::new());
+}
+
+impl StatusObj {
+	pub fn progress(label: impl Into<String>, progress: f32) -> Self {
+		Self {
+			label: Some(label.into()),
+			progress: Some(progress),
+			..Default::default()
+		}
+	}
+
+	pub fn log(line: impl Into<String>) -> Self {
+		Self {
+			log_line: Some(line.into()),
+			..Default::default()
+		}
+	}
+
+	pub fn error(message: impl Into<String>) -> Self {
+		Self {
+			error: Some(message.into()),
+			..Default::default()
+		}
+	}
+
+	pub fn complete() -> Self {
+		Self {
+			complete: true,
+			..Default::default()
+		}
+	}
+
+	pub fn emit(self, channel: &str) {
+		webview_emit!(channel, self);
+	}
+
+	/// Emits a `prompt_items` status on `channel` and blocks the calling
+	/// (background) thread until the frontend echoes a choice back via
+	/// [`resolve_status_prompt`]. Returns `None` only if the pending sender is
+	/// dropped without a reply, which happens when a later prompt on the same
+	/// channel replaces it; cancelling the task does not unblock the wait.
+	pub fn prompt(channel: &str, items: Vec<String>) -> Option<String> {
+		let (tx, rx) = mpsc::channel();
+		PENDING_PROMPTS.lock().insert(channel.to_string(), tx);
+
+		Self {
+			prompt_items: Some(items),
+			..Default::default()
+		}
+		.emit(channel);
+
+		rx.recv().ok()
+	}
+}
+
+#[tauri::command]
+pub fn resolve_status_prompt(channel: String, choice: String) {
+	if let Some(tx) = PENDING_PROMPTS.lock().remove(&channel) {
+		ignore! { tx.send(choice) };
+	}
+}